@@ -1,6 +1,6 @@
 use metrics::counter;
 use vector_lib::internal_event::InternalEvent;
-use vector_lib::internal_event::{error_stage, error_type};
+use vector_lib::internal_event::{error_stage, error_type, ComponentEventsDropped, UNINTENTIONAL};
 
 #[derive(Debug)]
 pub struct RedisReceiveEventError {
@@ -22,7 +22,7 @@ impl InternalEvent for RedisReceiveEventError {
             error = %self.error,
             error_code = %self.error_code,
             error_type = error_type::READER_FAILED,
-            stage = error_stage::SENDING,
+            stage = error_stage::RECEIVING,
             internal_log_rate_limit = true,
         );
         counter!(
@@ -34,3 +34,130 @@ impl InternalEvent for RedisReceiveEventError {
         .increment(1);
     }
 }
+
+#[derive(Debug)]
+pub struct RedisInvalidMessageError<'a> {
+    pub error: crate::Error,
+    pub key: &'a str,
+}
+
+impl InternalEvent for RedisInvalidMessageError<'_> {
+    fn emit(self) {
+        let reason = "Failed to read message payload.";
+
+        error!(
+            message = reason,
+            error = %self.error,
+            key = %self.key,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason });
+    }
+}
+
+#[derive(Debug)]
+pub struct RedisPayloadTooLargeError<'a> {
+    pub key: &'a str,
+    pub size: usize,
+    pub max_payload_bytes: usize,
+}
+
+impl InternalEvent for RedisPayloadTooLargeError<'_> {
+    fn emit(self) {
+        let reason = "Message payload exceeds the maximum size.";
+
+        error!(
+            message = reason,
+            key = %self.key,
+            size = %self.size,
+            max_payload_bytes = %self.max_payload_bytes,
+            error_code = "payload_too_large",
+            error_type = error_type::CONDITION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+
+        counter!(
+            "component_errors_total",
+            "error_code" => "payload_too_large",
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason });
+    }
+}
+
+#[derive(Debug)]
+pub struct RedisConnectionEstablished<'a> {
+    pub endpoint: &'a str,
+}
+
+impl InternalEvent for RedisConnectionEstablished<'_> {
+    fn emit(self) {
+        info!(message = "Connected.", endpoint = %self.endpoint);
+        counter!("connection_established_total").increment(1);
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("RedisConnectionEstablished")
+    }
+}
+
+#[derive(Debug)]
+pub struct RedisConnectionShutdown<'a> {
+    pub endpoint: &'a str,
+}
+
+impl InternalEvent for RedisConnectionShutdown<'_> {
+    fn emit(self) {
+        warn!(message = "Connection closed, reconnecting.", endpoint = %self.endpoint);
+        counter!("connection_shutdown_total").increment(1);
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("RedisConnectionShutdown")
+    }
+}
+
+#[derive(Debug)]
+pub struct RedisConnectionFailedError<'a> {
+    pub error: crate::Error,
+    pub endpoint: &'a str,
+}
+
+impl InternalEvent for RedisConnectionFailedError<'_> {
+    fn emit(self) {
+        error!(
+            message = "Failed to reconnect.",
+            error = %self.error,
+            endpoint = %self.endpoint,
+            error_code = "failed_reconnecting",
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "failed_reconnecting",
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("RedisConnectionFailedError")
+    }
+}
@@ -1,56 +1,303 @@
+use std::collections::HashMap;
+
 use futures_util::StreamExt;
+use redis::aio::PubSub;
+use regex::Regex;
 use snafu::{ResultExt, Snafu};
 
 use crate::{
-    internal_events::RedisReceiveEventError,
+    internal_events::{
+        RedisConnectionEstablished, RedisConnectionFailedError, RedisConnectionShutdown,
+        RedisInvalidMessageError,
+    },
     sources::{
-        redis::{ConnectionInfo, InputHandler},
+        redis::{
+            backoff_exponential, connection_hint, ChannelMessage, ChannelOption, ConnectionInfo,
+            HandleError, InputHandler, SubscribeMethod,
+        },
         Source,
     },
 };
 
 #[derive(Debug, Snafu)]
 enum BuildError {
-    #[snafu(display("Failed to create connection: {}", source))]
+    #[snafu(display("Failed to create connection: {}{}", source, connection_hint(source)))]
     Connection { source: redis::RedisError },
-    #[snafu(display("Failed to subscribe to channel: {}", source))]
-    Subscribe { source: redis::RedisError },
+    #[snafu(display("Failed to subscribe to channels {}: {}", channels, source))]
+    Subscribe {
+        channels: String,
+        source: redis::RedisError,
+    },
+    #[snafu(display("Invalid channel pattern {}: {}", pattern, source))]
+    Pattern {
+        pattern: String,
+        source: regex::Error,
+    },
 }
 
 impl InputHandler {
+    /// Subscribes to the configured channels, emitting keyspace notifications rather than decoding the messages
+    /// when `keyspace` is set.
     pub(super) async fn subscribe(
         mut self,
-        connection_info: ConnectionInfo,
+        options: ChannelOption,
+        keyspace: bool,
     ) -> crate::Result<Source> {
-        let conn = self
-            .client
+        let method = options.method;
+        let captures = PatternCaptures::new(&self.keys, options.captures)?;
+        let (mut pubsub_conn, mut connection_info) = self.pubsub(method).await?;
+        emit!(RedisConnectionEstablished {
+            endpoint: &connection_info.endpoint
+        });
+
+        Ok(Box::pin(async move {
+            let mut shutdown = self.cx.shutdown.clone();
+            'connection: loop {
+                let mut pubsub_stream = pubsub_conn.on_message();
+                loop {
+                    // Messages are only read between payloads, so the events of a message that has been read are
+                    // always sent before shutting down.
+                    let msg = tokio::select! {
+                        msg = pubsub_stream.next() => msg,
+                        _ = &mut shutdown => break 'connection,
+                    };
+                    // The stream only ends when the connection is dropped.
+                    let Some(msg) = msg else { break };
+
+                    match msg.get_payload::<Vec<u8>>() {
+                        Ok(payload) => {
+                            let channel = msg.get_channel_name();
+                            // For pattern subscriptions the key is the pattern that matched, otherwise it is the channel itself.
+                            let key = msg
+                                .get_pattern::<Option<String>>()
+                                .ok()
+                                .flatten()
+                                .unwrap_or_else(|| channel.to_owned());
+                            let message = ChannelMessage {
+                                channel,
+                                captures: captures.captures(&key, channel),
+                            };
+                            let res = if keyspace {
+                                self.handle_notification(&message, &key, payload).await
+                            } else {
+                                self.handle_line(payload.into(), &key, Some(&message), None)
+                                    .await
+                            };
+                            if let Err(HandleError::Closed) = res {
+                                break 'connection;
+                            }
+                        }
+                        Err(error) => emit!(RedisInvalidMessageError {
+                            error: error.into(),
+                            key: msg.get_channel_name(),
+                        }),
+                    }
+                }
+                drop(pubsub_stream);
+
+                emit!(RedisConnectionShutdown {
+                    endpoint: &connection_info.endpoint
+                });
+
+                let mut retry: u32 = 0;
+                (pubsub_conn, connection_info) = loop {
+                    retry += 1;
+                    tokio::select! {
+                        _ = backoff_exponential(retry) => {},
+                        _ = &mut shutdown => return Ok(()),
+                    }
+
+                    // Connecting has no timeout of its own, so it must not hold up a shutdown.
+                    let res = tokio::select! {
+                        res = self.pubsub(method) => res,
+                        _ = &mut shutdown => return Ok(()),
+                    };
+                    match res {
+                        Ok(res) => break res,
+                        Err(error) => emit!(RedisConnectionFailedError {
+                            error: error.into(),
+                            endpoint: &connection_info.endpoint
+                        }),
+                    }
+                };
+
+                emit!(RedisConnectionEstablished {
+                    endpoint: &connection_info.endpoint
+                });
+            }
+
+            self.unsubscribe(&mut pubsub_conn, method).await;
+            Ok(())
+        }))
+    }
+
+    /// Unsubscribes from all configured keys, so that the server stops sending messages before the connection is
+    /// closed.
+    async fn unsubscribe(&self, pubsub_conn: &mut PubSub, method: SubscribeMethod) {
+        let res = match method {
+            SubscribeMethod::Subscribe => pubsub_conn.unsubscribe(&self.keys).await,
+            SubscribeMethod::Psubscribe => pubsub_conn.punsubscribe(&self.keys).await,
+        };
+        if let Err(error) = res {
+            debug!(message = "Failed to unsubscribe from channels.", channels = ?self.keys, %error);
+        }
+    }
+
+    /// Opens a new Pub/Sub connection and subscribes it to all configured keys.
+    async fn pubsub(
+        &mut self,
+        method: SubscribeMethod,
+    ) -> Result<(PubSub, ConnectionInfo), BuildError> {
+        let client = self.client.resolve().await.context(ConnectionSnafu {})?;
+        let connection_info = ConnectionInfo::from(client.get_connection_info());
+        let conn = client
             .get_async_connection()
             .await
             .context(ConnectionSnafu {})?;
 
-        trace!(endpoint = %connection_info.endpoint.as_str(), "Connected.");
-
+        // All keys are subscribed to with a single command, as the reply to a command is read from the same stream
+        // as the messages. A message published to a key subscribed to by an earlier command could otherwise be read
+        // as the reply to a later one, and be lost. Only the first reply is read here, the confirmations for the other
+        // keys are skipped when reading messages.
         let mut pubsub_conn = conn.into_pubsub();
-        pubsub_conn
-            .subscribe(&self.key)
-            .await
-            .context(SubscribeSnafu {})?;
-        trace!(endpoint = %connection_info.endpoint.as_str(), channel = %self.key, "Subscribed to channel.");
+        match method {
+            SubscribeMethod::Subscribe => pubsub_conn.subscribe(&self.keys).await,
+            SubscribeMethod::Psubscribe => pubsub_conn.psubscribe(&self.keys).await,
+        }
+        .context(SubscribeSnafu {
+            channels: self.keys.join(", "),
+        })?;
+        trace!(endpoint = %connection_info.endpoint.as_str(), channels = ?self.keys, ?method, "Subscribed to channels.");
 
-        Ok(Box::pin(async move {
-            let shutdown = self.cx.shutdown.clone();
-            let mut pubsub_stream = pubsub_conn.on_message().take_until(shutdown);
-            while let Some(msg) = pubsub_stream.next().await {
-                match msg.get_payload::<String>() {
-                    Ok(line) => {
-                        if let Err(()) = self.handle_line(line).await {
-                            break;
+        Ok((pubsub_conn, connection_info))
+    }
+}
+
+/// Extracts the values matched by the wildcards of a pattern from the channels it matched.
+struct PatternCaptures {
+    names: Vec<String>,
+    patterns: HashMap<String, Regex>,
+}
+
+impl PatternCaptures {
+    fn new(patterns: &[String], names: Vec<String>) -> Result<Self, BuildError> {
+        let patterns = if names.is_empty() {
+            HashMap::new()
+        } else {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    pattern_regex(pattern)
+                        .context(PatternSnafu { pattern })
+                        .map(|regex| (pattern.clone(), regex))
+                })
+                .collect::<Result<_, _>>()?
+        };
+        Ok(Self { names, patterns })
+    }
+
+    /// Returns the field names paired with the values captured from `channel` by the wildcards of `pattern`.
+    fn captures<'a>(&'a self, pattern: &str, channel: &'a str) -> Vec<(&'a str, &'a str)> {
+        let Some(captures) = self
+            .patterns
+            .get(pattern)
+            .and_then(|regex| regex.captures(channel))
+        else {
+            return Vec::new();
+        };
+        self.names
+            .iter()
+            .zip(captures.iter().skip(1))
+            .filter_map(|(name, value)| Some((name.as_str(), value?.as_str())))
+            .collect()
+    }
+}
+
+/// Translates a glob-style Redis pattern into an anchored regular expression with a capture group for each `*`.
+fn pattern_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    let mut regex = String::from("(?s)^");
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => regex.push_str("(.*)"),
+            '?' => regex.push('.'),
+            '[' => {
+                regex.push('[');
+                if chars.as_str().starts_with('^') {
+                    chars.next();
+                    regex.push('^');
+                }
+                loop {
+                    match chars.next() {
+                        Some(']') | None => break,
+                        Some('-') => regex.push('-'),
+                        Some('\\') => {
+                            if let Some(c) = chars.next() {
+                                regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4])));
+                            }
                         }
+                        Some(c) => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
                     }
-                    Err(error) => emit!(RedisReceiveEventError::from(error)),
                 }
+                regex.push(']');
             }
-            Ok(())
-        }))
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4])));
+                }
+            }
+            c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn captures(pattern: &str, names: &[&str], channel: &str) -> Vec<(String, String)> {
+        let names = names.iter().map(|name| name.to_string()).collect();
+        PatternCaptures::new(&[pattern.to_owned()], names)
+            .unwrap()
+            .captures(pattern, channel)
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value.to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn captures_wildcards_in_order() {
+        assert_eq!(
+            captures("metrics.*.*", &["host", "metric"], "metrics.web01.cpu"),
+            vec![
+                ("host".to_owned(), "web01".to_owned()),
+                ("metric".to_owned(), "cpu".to_owned())
+            ]
+        );
+        assert_eq!(
+            captures("metrics.*.cpu", &["host"], "metrics.web01.cpu"),
+            vec![("host".to_owned(), "web01".to_owned())]
+        );
+    }
+
+    #[test]
+    fn captures_ignore_extra_names() {
+        assert_eq!(
+            captures("metrics.*", &["host", "metric"], "metrics.web01"),
+            vec![("host".to_owned(), "web01".to_owned())]
+        );
+    }
+
+    #[test]
+    fn pattern_regex_matches_like_redis() {
+        let regex = pattern_regex("h?llo.[ae]*.[^x]\\*").unwrap();
+        assert!(regex.is_match("hallo.a.y*"));
+        assert!(regex.is_match("hello.eee.z*"));
+        assert!(!regex.is_match("hllo.a.y*"));
+        assert!(!regex.is_match("hallo.b.y*"));
+        assert!(!regex.is_match("hallo.a.x*"));
+        assert!(!regex.is_match("hallo.a.yz"));
     }
 }
@@ -0,0 +1,130 @@
+use bytes::Bytes;
+use chrono::Utc;
+use smallvec::smallvec;
+use vector_lib::codecs::BytesDeserializer;
+use vector_lib::config::LegacyKey;
+use vector_lib::internal_event::{ByteSize, InternalEventHandle as _};
+use vector_lib::lookup::path;
+
+use super::{ChannelMessage, HandleError, InputHandler, RedisSourceConfig};
+use crate::internal_events::RedisInvalidMessageError;
+
+/// A keyspace notification, as published on a `__keyspace@<db>__:<key>` or a `__keyevent@<db>__:<event>` channel.
+#[derive(Debug, PartialEq)]
+struct Notification {
+    db: i64,
+    key: Bytes,
+    event: String,
+}
+
+impl InputHandler {
+    /// Emits an event for a keyspace notification, with the changed key as its message.
+    pub(super) async fn handle_notification(
+        &mut self,
+        message: &ChannelMessage<'_>,
+        key: &str,
+        mut payload: Vec<u8>,
+    ) -> Result<(), HandleError> {
+        let now = Utc::now();
+
+        self.bytes_received.emit(ByteSize(payload.len()));
+
+        let Some(len) = self.check_payload_size(payload.len(), key) else {
+            return Ok(());
+        };
+        let truncated = len < payload.len();
+        payload.truncate(len);
+
+        let Some(notification) = parse_notification(message.channel, payload) else {
+            emit!(RedisInvalidMessageError {
+                error: "Message is not a keyspace notification.".into(),
+                key: message.channel,
+            });
+            return Ok(());
+        };
+
+        let mut log = BytesDeserializer.parse_single(notification.key, self.log_namespace);
+        self.log_namespace.insert_source_metadata(
+            RedisSourceConfig::NAME,
+            &mut log,
+            self.redis_event.as_ref().map(LegacyKey::InsertIfEmpty),
+            path!("event"),
+            notification.event,
+        );
+        self.log_namespace.insert_source_metadata(
+            RedisSourceConfig::NAME,
+            &mut log,
+            self.redis_db.as_ref().map(LegacyKey::InsertIfEmpty),
+            path!("db"),
+            notification.db,
+        );
+        if truncated {
+            self.mark_truncated(&mut log);
+        }
+
+        self.send_events(smallvec![log.into()], now, key, Some(message), None)
+            .await
+    }
+}
+
+/// Parses a notification from the channel it was published on and its payload.
+///
+/// For `__keyspace@<db>__:<key>` channels the payload is the event, and for `__keyevent@<db>__:<event>` channels it is
+/// the key.
+fn parse_notification(channel: &str, payload: Vec<u8>) -> Option<Notification> {
+    let (prefix, rest) = channel.split_once('@')?;
+    let (db, name) = rest.split_once("__:")?;
+    let db = db.parse().ok()?;
+    match prefix {
+        "__keyspace" => Some(Notification {
+            db,
+            key: Bytes::copy_from_slice(name.as_bytes()),
+            event: String::from_utf8_lossy(&payload).into_owned(),
+        }),
+        "__keyevent" => Some(Notification {
+            db,
+            key: payload.into(),
+            event: name.to_owned(),
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_keyevent_notification() {
+        assert_eq!(
+            parse_notification("__keyevent@0__:expired", b"session:42".to_vec()),
+            Some(Notification {
+                db: 0,
+                key: Bytes::from_static(b"session:42"),
+                event: "expired".to_owned(),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_keyspace_notification() {
+        assert_eq!(
+            parse_notification("__keyspace@3__:user:__:1", b"set".to_vec()),
+            Some(Notification {
+                db: 3,
+                key: Bytes::from_static(b"user:__:1"),
+                event: "set".to_owned(),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_other_channels() {
+        assert_eq!(parse_notification("metrics.cpu", b"1".to_vec()), None);
+        assert_eq!(
+            parse_notification("__keyevent@x__:del", b"k".to_vec()),
+            None
+        );
+        assert_eq!(parse_notification("__other@0__:del", b"k".to_vec()), None);
+    }
+}
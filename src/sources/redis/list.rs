@@ -1,37 +1,76 @@
-use redis::{aio::ConnectionManager, AsyncCommands, ErrorKind, RedisError, RedisResult};
+use futures::{FutureExt, StreamExt};
+use redis::{aio::ConnectionManager, AsyncCommands, Direction, ErrorKind, RedisError, RedisResult};
 use snafu::{ResultExt, Snafu};
-use std::time::Duration;
+use vector_lib::finalizer::UnorderedFinalizer;
 
-use super::{InputHandler, Method};
-use crate::{internal_events::RedisReceiveEventError, sources::Source};
+use super::{backoff_exponential, connection_hint, HandleError, InputHandler, ListOption, Method};
+use crate::{
+    event::{BatchNotifier, BatchStatus},
+    internal_events::RedisReceiveEventError,
+    sources::Source,
+};
 
 #[derive(Debug, Snafu)]
 enum BuildError {
-    #[snafu(display("Failed to create connection: {}", source))]
+    #[snafu(display("Failed to create connection: {}{}", source, connection_hint(source)))]
     Connection { source: RedisError },
 }
 
+/// An element in the processing list waiting for its events to be delivered before it is removed.
+struct FinalizerEntry {
+    key: String,
+    payload: Vec<u8>,
+}
+
+type Finalizer = UnorderedFinalizer<FinalizerEntry>;
+
 impl InputHandler {
-    pub(super) async fn watch(mut self, method: Method) -> crate::Result<Source> {
+    pub(super) async fn watch(
+        mut self,
+        options: ListOption,
+        acknowledgements: bool,
+    ) -> crate::Result<Source> {
         let mut conn = self
-            .client
-            .get_connection_manager()
+            .connection_manager()
             .await
             .context(ConnectionSnafu {})?;
 
         Ok(Box::pin(async move {
             let mut shutdown = self.cx.shutdown.clone();
+            // Shutdown is handled here rather than by the finalizer, so that the acknowledgements of the elements
+            // still being delivered can be processed before returning.
+            let (finalizer, mut ack_stream) =
+                Finalizer::maybe_new(acknowledgements && options.processing_list.is_some(), None);
+
+            if let Some(processing_list) = &options.processing_list {
+                // Elements left in the processing list were never delivered, so they are read again first.
+                if let Err(error) =
+                    requeue(&mut conn, processing_list, &self.keys[0], options.method).await
+                {
+                    emit!(RedisReceiveEventError::from(error));
+                }
+            }
+
             let mut retry: u32 = 0;
             loop {
-                let res = match method {
-                    Method::Rpop => tokio::select! {
-                        res = brpop(&mut conn, &self.key) => res,
-                        _ = &mut shutdown => break
-                    },
-                    Method::Lpop => tokio::select! {
-                        res = blpop(&mut conn, &self.key) => res,
+                if let Some(processing_list) = &options.processing_list {
+                    while let Some(Some((status, entry))) = ack_stream.next().now_or_never() {
+                        handle_ack(&mut conn, processing_list, options.method, status, entry).await;
+                    }
+                }
+
+                // A pop is only cancelled on shutdown when it could otherwise block forever, as the element popped by a
+                // cancelled command is lost, or left in the processing list without being forwarded.
+                let res = if !options.timeout_secs.is_zero() {
+                    if shutdown.clone().now_or_never().is_some() {
+                        break;
+                    }
+                    pop(&mut conn, &self.keys, &options).await
+                } else {
+                    tokio::select! {
+                        res = pop(&mut conn, &self.keys, &options) => res,
                         _ = &mut shutdown => break
-                    },
+                    }
                 };
 
                 match res {
@@ -41,39 +80,188 @@ impl InputHandler {
 
                         emit!(RedisReceiveEventError::from(err));
 
-                        if kind == ErrorKind::IoError {
+                        // A master that was demoted by a failover rejects the pop as read-only.
+                        let failover = self.client.is_sentinel()
+                            && matches!(kind, ErrorKind::IoError | ErrorKind::ReadOnly);
+                        if kind == ErrorKind::IoError || failover {
                             retry += 1;
-                            backoff_exponential(retry).await
+                            tokio::select! {
+                                _ = backoff_exponential(retry) => {},
+                                _ = &mut shutdown => break,
+                            }
+                        }
+                        // The connection manager reconnects to the same server on its own, so it only needs to be
+                        // replaced when the master may have moved. Connecting has no timeout of its own, so it must
+                        // not hold up a shutdown.
+                        if failover {
+                            let res = tokio::select! {
+                                res = self.connection_manager() => res,
+                                _ = &mut shutdown => break,
+                            };
+                            match res {
+                                Ok(new_conn) => conn = new_conn,
+                                Err(error) => emit!(RedisReceiveEventError::from(error)),
+                            }
                         }
                     }
-                    Ok(line) => {
+                    // The pop timed out without an element being available.
+                    Ok(None) => {}
+                    Ok(Some((key, payload))) => {
                         if retry > 0 {
                             retry = 0
                         }
-                        if let Err(()) = self.handle_line(line).await {
+                        let res = match &options.processing_list {
+                            Some(processing_list) => {
+                                self.handle_processing(
+                                    &mut conn,
+                                    processing_list,
+                                    finalizer.as_ref(),
+                                    key,
+                                    payload,
+                                )
+                                .await
+                            }
+                            None => self.handle_line(payload.into(), &key, None, None).await,
+                        };
+                        if let Err(HandleError::Closed) = res {
                             break;
                         }
                     }
                 }
             }
+
+            // No more elements are popped, but the elements whose events are still being delivered are removed from
+            // the processing list before returning.
+            if let (Some(finalizer), Some(processing_list)) = (finalizer, &options.processing_list)
+            {
+                drop(finalizer);
+                while let Some((status, entry)) = ack_stream.next().await {
+                    handle_ack(&mut conn, processing_list, options.method, status, entry).await;
+                }
+            }
             Ok(())
         }))
     }
+
+    /// Forwards an element that was moved into the processing list.
+    ///
+    /// With acknowledgements enabled, the element is removed from the processing list once its events have been
+    /// delivered. Otherwise, it is removed as soon as its events have been sent.
+    async fn handle_processing(
+        &mut self,
+        conn: &mut ConnectionManager,
+        processing_list: &str,
+        finalizer: Option<&Finalizer>,
+        key: String,
+        payload: Vec<u8>,
+    ) -> Result<(), HandleError> {
+        let (batch, receiver) = finalizer
+            .map(|_| BatchNotifier::new_with_receiver())
+            .unzip();
+        // On shutdown, the element is left in the processing list and read again on the next start.
+        if let Err(HandleError::Closed) = self
+            .handle_line(payload.clone().into(), &key, None, batch.as_ref())
+            .await
+        {
+            return Err(HandleError::Closed);
+        }
+        match (finalizer, receiver) {
+            (Some(finalizer), Some(receiver)) => {
+                finalizer.add(FinalizerEntry { key, payload }, receiver)
+            }
+            _ => lrem(conn, processing_list, &payload).await,
+        }
+        Ok(())
+    }
 }
 
-async fn backoff_exponential(exp: u32) {
-    let ms = if exp <= 4 { 2_u64.pow(exp + 5) } else { 1000 };
-    tokio::time::sleep(Duration::from_millis(ms)).await;
+/// Pops the next element, returning the key it was popped from, or `None` if the pop timed out.
+async fn pop(
+    conn: &mut ConnectionManager,
+    keys: &[String],
+    options: &ListOption,
+) -> RedisResult<Option<(String, Vec<u8>)>> {
+    let timeout = options.timeout_secs.as_secs_f64();
+    match (&options.processing_list, options.method) {
+        (Some(processing_list), method) => {
+            let payload: Option<Vec<u8>> = conn
+                .blmove(
+                    &keys[0],
+                    processing_list,
+                    direction(method),
+                    Direction::Left,
+                    timeout,
+                )
+                .await?;
+            Ok(payload.map(|payload| (keys[0].clone(), payload)))
+        }
+        (None, Method::Rpop) => conn.brpop(keys, timeout).await,
+        (None, Method::Lpop) => conn.blpop(keys, timeout).await,
+    }
 }
 
-async fn brpop(conn: &mut ConnectionManager, key: &str) -> RedisResult<String> {
-    conn.brpop(key, 0.0)
-        .await
-        .map(|(_, value): (String, String)| value)
+/// Removes a delivered element from the processing list, or pushes it back onto its list so that it is retried.
+///
+/// Rejected elements are left in the processing list rather than retried right away, as they are likely to be
+/// rejected again. They are read again when the source next starts.
+async fn handle_ack(
+    conn: &mut ConnectionManager,
+    processing_list: &str,
+    method: Method,
+    status: BatchStatus,
+    entry: FinalizerEntry,
+) {
+    match status {
+        BatchStatus::Delivered => lrem(conn, processing_list, &entry.payload).await,
+        BatchStatus::Errored => {
+            let res: RedisResult<()> = match method {
+                Method::Rpop => conn.rpush(&entry.key, &entry.payload).await,
+                Method::Lpop => conn.lpush(&entry.key, &entry.payload).await,
+            };
+            match res {
+                Ok(()) => lrem(conn, processing_list, &entry.payload).await,
+                // The element stays in the processing list, so it is still read again on the next start.
+                Err(error) => emit!(RedisReceiveEventError::from(error)),
+            }
+        }
+        BatchStatus::Rejected => {
+            warn!(
+                message = "List element was rejected and is left in the processing list.",
+                key = %entry.key,
+                processing_list = %processing_list,
+                internal_log_rate_limit = true,
+            );
+        }
+    }
 }
 
-async fn blpop(conn: &mut ConnectionManager, key: &str) -> RedisResult<String> {
-    conn.blpop(key, 0.0)
-        .await
-        .map(|(_, value): (String, String)| value)
+async fn lrem(conn: &mut ConnectionManager, processing_list: &str, payload: &[u8]) {
+    if let Err(error) = conn.lrem::<_, _, ()>(processing_list, 1, payload).await {
+        emit!(RedisReceiveEventError::from(error));
+    }
+}
+
+/// Moves every element of the processing list back onto the end of `key` that is popped from, oldest last so
+/// that it is read first.
+async fn requeue(
+    conn: &mut ConnectionManager,
+    processing_list: &str,
+    key: &str,
+    method: Method,
+) -> RedisResult<()> {
+    loop {
+        let moved: Option<Vec<u8>> = conn
+            .lmove(processing_list, key, Direction::Left, direction(method))
+            .await?;
+        if moved.is_none() {
+            return Ok(());
+        }
+    }
+}
+
+const fn direction(method: Method) -> Direction {
+    match method {
+        Method::Lpop => Direction::Left,
+        Method::Rpop => Direction::Right,
+    }
 }
@@ -0,0 +1,388 @@
+use futures::{FutureExt, StreamExt};
+use redis::{
+    aio::ConnectionManager,
+    from_redis_value,
+    streams::{StreamId, StreamRangeReply, StreamReadOptions, StreamReadReply},
+    AsyncCommands, ErrorKind, RedisError, RedisResult, Value,
+};
+use snafu::{ResultExt, Snafu};
+use vector_lib::finalizer::UnorderedFinalizer;
+
+use super::{backoff_exponential, connection_hint, HandleError, InputHandler, StreamOption};
+use crate::{
+    event::{BatchNotifier, BatchStatus},
+    internal_events::{RedisInvalidMessageError, RedisReceiveEventError},
+    shutdown::ShutdownSignal,
+    sources::Source,
+};
+
+/// How long a single `XREADGROUP` blocks waiting for new entries.
+///
+/// Reads are never cancelled once sent, as that would leave the entries they return pending without being
+/// forwarded, so this bounds how long acknowledgements wait to be processed.
+const READ_BLOCK_MS: usize = 1000;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Failed to create connection: {}{}", source, connection_hint(source)))]
+    Connection { source: RedisError },
+    #[snafu(display("Failed to create consumer group for stream {}: {}", stream, source))]
+    Group { stream: String, source: RedisError },
+}
+
+/// A stream entry waiting for its events to be delivered before it is acknowledged.
+struct FinalizerEntry {
+    key: String,
+    id: String,
+}
+
+type Finalizer = UnorderedFinalizer<FinalizerEntry>;
+
+impl InputHandler {
+    pub(super) async fn read_stream(
+        mut self,
+        options: StreamOption,
+        acknowledgements: bool,
+    ) -> crate::Result<Source> {
+        let mut conn = self
+            .connection_manager()
+            .await
+            .context(ConnectionSnafu {})?;
+
+        for key in &self.keys {
+            create_group(&mut conn, key, &options.group, &options.start_id)
+                .await
+                .context(GroupSnafu { stream: key })?;
+        }
+
+        Ok(Box::pin(async move {
+            let mut shutdown = self.cx.shutdown.clone();
+            // Shutdown is handled here rather than by the finalizer, so that the acknowledgements of the entries still
+            // being delivered can be processed before returning.
+            let (finalizer, mut ack_stream) = Finalizer::maybe_new(acknowledgements, None);
+
+            // Entries that were read but never acknowledged, either by a previous run of this consumer or by a
+            // consumer that has since gone away, are delivered before any new entries. Entries that are not read
+            // because of a shutdown stay pending, and are read again on the next start.
+            let mut closed = matches!(
+                self.read_pending(&mut conn, &options, finalizer.as_ref(), &shutdown)
+                    .await,
+                Err(HandleError::Closed)
+            );
+
+            let read_options = StreamReadOptions::default()
+                .group(&options.group, &options.consumer)
+                .count(options.count.get())
+                .block(READ_BLOCK_MS);
+            let ids = vec![">"; self.keys.len()];
+            let mut retry: u32 = 0;
+            loop {
+                while let Some(Some((status, entry))) = ack_stream.next().now_or_never() {
+                    if let Err(HandleError::Closed) = self
+                        .handle_ack(&mut conn, &options, finalizer.as_ref(), status, entry)
+                        .await
+                    {
+                        closed = true;
+                        break;
+                    }
+                }
+
+                if closed || shutdown.clone().now_or_never().is_some() {
+                    break;
+                }
+
+                let res: RedisResult<StreamReadReply> = conn
+                    .xread_options(self.keys.as_slice(), ids.as_slice(), &read_options)
+                    .await;
+
+                match res {
+                    Err(error) => {
+                        let kind = error.kind();
+
+                        emit!(RedisReceiveEventError::from(error));
+
+                        // A master that was demoted by a failover rejects the read as read-only.
+                        let failover = self.client.is_sentinel()
+                            && matches!(kind, ErrorKind::IoError | ErrorKind::ReadOnly);
+                        if kind == ErrorKind::IoError || failover {
+                            retry += 1;
+                            tokio::select! {
+                                _ = backoff_exponential(retry) => {},
+                                _ = &mut shutdown => break,
+                            }
+                        }
+                        // The connection manager reconnects to the same server on its own, so it only needs to be
+                        // replaced when the master may have moved. Connecting has no timeout of its own, so it must
+                        // not hold up a shutdown.
+                        if failover {
+                            let res = tokio::select! {
+                                res = self.connection_manager() => res,
+                                _ = &mut shutdown => break,
+                            };
+                            match res {
+                                Ok(new_conn) => conn = new_conn,
+                                Err(error) => emit!(RedisReceiveEventError::from(error)),
+                            }
+                        }
+                    }
+                    Ok(reply) => {
+                        if retry > 0 {
+                            retry = 0
+                        }
+                        let entries = reply
+                            .keys
+                            .into_iter()
+                            .map(|stream| (stream.key, stream.ids))
+                            .collect();
+                        if let Err(HandleError::Closed) = self
+                            .handle_entries(&mut conn, &options, finalizer.as_ref(), entries)
+                            .await
+                        {
+                            break;
+                        }
+                    }
+                }
+            }
+
+            // No more entries are read, but the entries whose events are still being delivered are acknowledged
+            // before returning. Entries that are not delivered are left pending, to be read again on the next start.
+            if let Some(finalizer) = finalizer {
+                drop(finalizer);
+                while let Some((status, entry)) = ack_stream.next().await {
+                    if status == BatchStatus::Delivered {
+                        xack(&mut conn, &entry.key, &options.group, &[entry.id]).await;
+                    }
+                }
+            }
+            Ok(())
+        }))
+    }
+
+    /// Delivers this consumer's own pending entries, then claims and delivers the entries that have been pending
+    /// for other consumers for longer than `min_idle_ms`.
+    ///
+    /// Each batch of `count` entries is delivered as soon as it is read, so that a large number of pending entries
+    /// is never held in memory at once, and `max_in_flight` applies to them. Shutdown is only checked between
+    /// batches, so that the events of the entries that were read are always sent.
+    async fn read_pending(
+        &mut self,
+        conn: &mut ConnectionManager,
+        options: &StreamOption,
+        finalizer: Option<&Finalizer>,
+        shutdown: &ShutdownSignal,
+    ) -> Result<(), HandleError> {
+        let read_options = StreamReadOptions::default()
+            .group(&options.group, &options.consumer)
+            .count(options.count.get());
+        for key in self.keys.clone() {
+            let mut start = "0".to_owned();
+            loop {
+                if shutdown.clone().now_or_never().is_some() {
+                    return Ok(());
+                }
+                let reply: RedisResult<StreamReadReply> =
+                    conn.xread_options(&[&key], &[&start], &read_options).await;
+                let ids: Vec<StreamId> = match reply {
+                    Ok(reply) => reply
+                        .keys
+                        .into_iter()
+                        .flat_map(|stream| stream.ids)
+                        .collect(),
+                    Err(error) => {
+                        emit!(RedisReceiveEventError::from(error));
+                        return Ok(());
+                    }
+                };
+                match ids.last() {
+                    Some(last) => start = last.id.clone(),
+                    None => break,
+                }
+                self.handle_entries(conn, options, finalizer, vec![(key.clone(), ids)])
+                    .await?;
+            }
+
+            let mut start = "0-0".to_owned();
+            loop {
+                if shutdown.clone().now_or_never().is_some() {
+                    return Ok(());
+                }
+                let (next, claimed) = match autoclaim(conn, &key, options, &start).await {
+                    Ok(res) => res,
+                    Err(error) => {
+                        emit!(RedisReceiveEventError::from(error));
+                        return Ok(());
+                    }
+                };
+                if !claimed.is_empty() {
+                    self.handle_entries(conn, options, finalizer, vec![(key.clone(), claimed)])
+                        .await?;
+                }
+                if next == "0-0" {
+                    break;
+                }
+                start = next;
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes and forwards the given stream entries.
+    ///
+    /// With acknowledgements enabled, each entry is acknowledged once its events have been delivered. Otherwise,
+    /// entries are acknowledged as soon as their events have been sent.
+    async fn handle_entries(
+        &mut self,
+        conn: &mut ConnectionManager,
+        options: &StreamOption,
+        finalizer: Option<&Finalizer>,
+        entries: Vec<(String, Vec<StreamId>)>,
+    ) -> Result<(), HandleError> {
+        for (key, ids) in entries {
+            let mut acks = Vec::with_capacity(ids.len());
+            for entry in ids {
+                match entry.get::<Vec<u8>>(&options.field) {
+                    Some(payload) => {
+                        let (batch, receiver) = match finalizer {
+                            Some(_) => {
+                                let (batch, receiver) = BatchNotifier::new_with_receiver();
+                                (Some(batch), Some(receiver))
+                            }
+                            None => (None, None),
+                        };
+                        // Entries that fail to decode are acknowledged like any other, as reading them again
+                        // would fail the same way.
+                        if let Err(HandleError::Closed) = self
+                            .handle_line(payload.into(), &key, None, batch.as_ref())
+                            .await
+                        {
+                            return Err(HandleError::Closed);
+                        }
+                        if let (Some(finalizer), Some(receiver)) = (finalizer, receiver) {
+                            finalizer.add(
+                                FinalizerEntry {
+                                    key: key.clone(),
+                                    id: entry.id,
+                                },
+                                receiver,
+                            );
+                            continue;
+                        }
+                    }
+                    // Entries without the payload field can never be decoded, so they are acknowledged to keep them
+                    // from being redelivered forever.
+                    None => emit!(RedisInvalidMessageError {
+                        error: format!(
+                            "Stream entry {} is missing the `{}` field.",
+                            entry.id, options.field
+                        )
+                        .into(),
+                        key: &key,
+                    }),
+                }
+                acks.push(entry.id);
+            }
+
+            xack(conn, &key, &options.group, &acks).await;
+        }
+        Ok(())
+    }
+
+    /// Acknowledges a delivered entry, or reads it again so that it is retried.
+    ///
+    /// Rejected entries are left pending rather than retried right away, as they are likely to be rejected
+    /// again. They are delivered again when the source next starts.
+    async fn handle_ack(
+        &mut self,
+        conn: &mut ConnectionManager,
+        options: &StreamOption,
+        finalizer: Option<&Finalizer>,
+        status: BatchStatus,
+        entry: FinalizerEntry,
+    ) -> Result<(), HandleError> {
+        match status {
+            BatchStatus::Delivered => {
+                xack(conn, &entry.key, &options.group, &[entry.id]).await;
+                Ok(())
+            }
+            BatchStatus::Errored => {
+                let reply: RedisResult<StreamRangeReply> =
+                    conn.xrange(&entry.key, &entry.id, &entry.id).await;
+                match reply {
+                    Ok(reply) => {
+                        self.handle_entries(conn, options, finalizer, vec![(entry.key, reply.ids)])
+                            .await
+                    }
+                    Err(error) => {
+                        emit!(RedisReceiveEventError::from(error));
+                        Ok(())
+                    }
+                }
+            }
+            BatchStatus::Rejected => {
+                warn!(
+                    message = "Stream entry was rejected and is left pending.",
+                    key = %entry.key,
+                    id = %entry.id,
+                    internal_log_rate_limit = true,
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+async fn xack(conn: &mut ConnectionManager, key: &str, group: &str, ids: &[String]) {
+    if ids.is_empty() {
+        return;
+    }
+    if let Err(error) = conn.xack::<_, _, _, ()>(key, group, ids).await {
+        emit!(RedisReceiveEventError::from(error));
+    }
+}
+
+async fn create_group(
+    conn: &mut ConnectionManager,
+    key: &str,
+    group: &str,
+    start_id: &str,
+) -> RedisResult<()> {
+    match conn
+        .xgroup_create_mkstream::<_, _, _, ()>(key, group, start_id)
+        .await
+    {
+        // The group already exists, which is the expected case after the first start.
+        Err(error) if error.code() == Some("BUSYGROUP") => Ok(()),
+        res => res,
+    }
+}
+
+async fn autoclaim(
+    conn: &mut ConnectionManager,
+    key: &str,
+    options: &StreamOption,
+    start: &str,
+) -> RedisResult<(String, Vec<StreamId>)> {
+    // `XAUTOCLAIM` replies with the cursor to continue from, the claimed entries and, since Redis 7, the IDs of
+    // entries that no longer exist. The latter are not needed here.
+    let reply: Vec<Value> = redis::cmd("XAUTOCLAIM")
+        .arg(key)
+        .arg(&options.group)
+        .arg(&options.consumer)
+        .arg(options.min_idle_ms)
+        .arg(start)
+        .arg("COUNT")
+        .arg(options.count.get())
+        .query_async(conn)
+        .await?;
+
+    let next: String = match reply.first() {
+        Some(value) => from_redis_value(value)?,
+        None => "0-0".to_owned(),
+    };
+    let claimed: StreamRangeReply = match reply.get(1) {
+        Some(value) => from_redis_value(value)?,
+        None => StreamRangeReply::default(),
+    };
+
+    Ok((next, claimed.ids))
+}
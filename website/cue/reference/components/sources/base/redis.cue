@@ -1,8 +1,66 @@
 package metadata
 
 base: components: sources: redis: configuration: {
+	acknowledgements: {
+		deprecated: true
+		description: """
+			Controls how acknowledgements are handled by this source.
+
+			This setting is **deprecated** in favor of enabling `acknowledgements` at the [global][global_acks] or sink level.
+
+			Enabling or disabling acknowledgements at the source level has **no effect** on acknowledgement behavior.
+
+			See [End-to-end Acknowledgements][e2e_acks] for more information on how event acknowledgement is handled.
+
+			[global_acks]: https://vector.dev/docs/reference/configuration/global-options/#acknowledgements
+			[e2e_acks]: https://vector.dev/docs/about/under-the-hood/architecture/end-to-end-acknowledgements/
+			"""
+		required: false
+		type: object: options: enabled: {
+			description: "Whether or not end-to-end acknowledgements are enabled for this source."
+			required:    false
+			type: bool: {}
+		}
+	}
+	channel: {
+		description: "Options for the Redis `channel` data type."
+		required:    false
+		type: object: options: {
+			captures: {
+				description: """
+					Names of the fields to set from the wildcards of the pattern that matched the channel.
+
+					Each `*` in the pattern is captured, in order, into the field at the same position in this list. For
+					example, with the pattern `metrics.*.cpu` and `captures = ["host"]`, a message published to
+					`metrics.web01.cpu` has its `host` field set to `web01`.
+
+					Only valid for the `channel` data type, when `method` is `psubscribe`.
+					"""
+				required: false
+				type: array: {
+					default: []
+					items: type: string: examples: ["host"]
+				}
+			}
+			method: {
+				description: "Method for subscribing to the `channel` data type."
+				required:    false
+				type: string: {
+					default: "subscribe"
+					enum: {
+						psubscribe: """
+							Subscribe to all channels matching the glob-style pattern in `key`.
+
+							The concrete channel each message was published to is added to the event metadata.
+							"""
+						subscribe: "Subscribe to the channel named by `key`."
+					}
+				}
+			}
+		}
+	}
 	data_type: {
-		description: "The Redis data type (`list` or `channel`) to use."
+		description: "The Redis data type (`list`, `channel`, `stream`, or `keyspace`) to use."
 		required:    false
 		type: string: {
 			default: "list"
@@ -12,10 +70,33 @@ base: components: sources: redis: configuration: {
 
 					This is based on Redis' Pub/Sub capabilities.
 					"""
+				keyspace: """
+					The `keyspace` data type.
+
+					An event is emitted for each keyspace notification published on the channels matching the patterns in
+					`key` and `keys`, which default to all `__keyevent@<db>__:*` channels of the configured database.
+					Notifications must be enabled on the server with `notify-keyspace-events`.
+					"""
 				list: "The `list` data type."
+				stream: """
+					The `stream` data type.
+
+					Entries are read with a consumer group and acknowledged once they have been forwarded.
+					"""
 			}
 		}
 	}
+	db: {
+		description: """
+			The Redis database to select, overriding the database set in `url`.
+
+			Pub/Sub channels are not scoped to a database, so for the `channel` data type this only matters for keyspace
+			notification channels. For the `keyspace` data type, the default pattern subscribes to the notifications of
+			this database. The source fails to start if the server rejects the database index as out of range.
+			"""
+		required: false
+		type: uint: examples: [0]
+	}
 	decoding: {
 		description: "Configures how events are decoded from raw bytes."
 		required:    false
@@ -442,42 +523,337 @@ base: components: sources: redis: configuration: {
 		}
 	}
 	key: {
-		description: "The Redis key to read messages from."
-		required:    true
-		type: string: examples: [
-			"vector",
-		]
+		description: """
+			The Redis key to read messages from.
+
+			At least one of `key` or `keys` must be set, except for the `keyspace` data type.
+			"""
+		required: false
+		type: string: {
+			default: ""
+			examples: [
+				"vector",
+			]
+		}
+	}
+	keys: {
+		description: """
+			Additional Redis keys to read messages from.
+
+			For the `list` data type, all of the lists are popped from with a single blocking command. For the
+			`channel` data type, all of the channels are subscribed to over a single connection.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["vector-a", "vector-b"]
+		}
 	}
 	list: {
 		description: "Options for the Redis `list` data type."
 		required:    false
-		type: object: options: method: {
-			description: "Method for getting events from the `list` data type."
-			required:    true
-			type: string: enum: {
-				lpop: "Pop messages from the head of the list."
-				rpop: "Pop messages from the tail of the list."
+		type: object: options: {
+			method: {
+				description: "Method for getting events from the `list` data type."
+				required:    true
+				type: string: enum: {
+					lpop: "Pop messages from the head of the list."
+					rpop: "Pop messages from the tail of the list."
+				}
+			}
+			processing_list: {
+				description: """
+					The list to move each element into while its events are being delivered.
+
+					When set, elements are moved into this list with `BLMOVE`, which requires Redis 6.2 or later, and are only
+					removed from it once their events have been sent or, with acknowledgements enabled, delivered. Elements
+					whose delivery fails are pushed back onto the list they were read from. Elements left in this list when
+					the source stopped are moved back when it starts again.
+
+					Only a single key can be read from, and each instance of the source must use its own processing list.
+					"""
+				required: false
+				type: string: examples: ["vector-processing"]
 			}
+			timeout_secs: {
+				description: """
+					The maximum amount of time, in seconds, that a single blocking pop waits for an element.
+
+					When no element is available in time, the pop is sent again. A value of `0` waits indefinitely, which can
+					not be used together with `processing_list`.
+					"""
+				required: false
+				type: float: {
+					default: 1.0
+					unit:    "seconds"
+				}
+			}
+		}
+	}
+	max_in_flight: {
+		description: """
+			The maximum number of events that can be in flight, having been read from Redis but not yet delivered by
+			the sinks.
+
+			When the limit is reached, the source stops reading from Redis until some of the events have been
+			delivered. By default, there is no limit.
+			"""
+		required: false
+		type: uint: unit: "events"
+	}
+	max_payload_bytes: {
+		description: """
+			The maximum size of a message payload.
+
+			Messages with a larger payload are dropped, unless `truncate_payloads` is set. By default, there is no limit.
+			"""
+		required: false
+		type: uint: unit: "bytes"
+	}
+	password: {
+		description: """
+			The password to authenticate with, overriding the password set in `url`.
+
+			Unlike in `url`, it does not need to be URL-encoded.
+			"""
+		required: false
+		type: string: examples: ["${REDIS_PASSWORD}"]
+	}
+	redis_channel: {
+		description: """
+			Overrides the name of the log field used to add the channel to each event.
+
+			The value is the channel that the message was published to. This is only added for the `channel` data
+			type.
+
+			By default, `"redis_channel"` is used.
+			"""
+		required: false
+		type: string: {
+			default: "redis_channel"
+			examples: ["redis_channel"]
+		}
+	}
+	redis_db: {
+		description: """
+			Overrides the name of the log field used to add the database of the changed key to each event.
+
+			This is only added for the `keyspace` data type.
+
+			By default, `"redis_db"` is used.
+			"""
+		required: false
+		type: string: {
+			default: "redis_db"
+			examples: ["redis_db"]
+		}
+	}
+	redis_event: {
+		description: """
+			Overrides the name of the log field used to add the keyspace notification event to each event.
+
+			The value is the name of the event, such as `set` or `expired`. This is only added for the `keyspace` data
+			type.
+
+			By default, `"redis_event"` is used.
+			"""
+		required: false
+		type: string: {
+			default: "redis_event"
+			examples: ["redis_event"]
 		}
 	}
 	redis_key: {
 		description: """
 			Sets the name of the log field to use to add the key to each event.
 
-			The value is the Redis key that the event was read from.
+			The value is the Redis key that the event was read from. When subscribing with `psubscribe`, this is the
+			pattern that matched the channel.
 
 			By default, this is not set and the field is not automatically added.
 			"""
 		required: false
 		type: string: examples: ["redis_key"]
 	}
+	sentinel: {
+		description: "Options for discovering the Redis master through Redis Sentinel."
+		required:    false
+		type: object: options: {
+			master: {
+				description: "The name of the master, as monitored by the sentinels."
+				required:    true
+				type: string: examples: ["mymaster"]
+			}
+			urls: {
+				description: "The URLs of the sentinels to discover the master from."
+				required:    true
+				type: array: items: type: string: examples: ["redis://127.0.0.1:26379"]
+			}
+		}
+	}
+	stream: {
+		description: "Options for the Redis `stream` data type."
+		required:    false
+		type: object: options: {
+			consumer: {
+				description: """
+					The name of the consumer within the group.
+
+					Each instance of the source reading from the same group must use a unique name.
+					"""
+				required: true
+				type: string: examples: ["vector-0"]
+			}
+			count: {
+				description: "The maximum number of entries to read in a single request."
+				required:    false
+				type: uint: default: 100
+			}
+			field: {
+				description: "The entry field containing the payload to decode."
+				required:    false
+				type: string: default: "message"
+			}
+			group: {
+				description: """
+					The consumer group to read the stream with.
+
+					The group, and the stream itself, are created if they do not already exist.
+					"""
+				required: true
+				type: string: examples: ["vector"]
+			}
+			min_idle_ms: {
+				description: """
+					The minimum time, in milliseconds, that an entry must have been pending for another consumer before it is
+					claimed on startup.
+
+					This allows entries read by a consumer that has since gone away to be delivered.
+					"""
+				required: false
+				type: uint: {
+					default: 60000
+					unit:    "milliseconds"
+				}
+			}
+			start_id: {
+				description: """
+					The ID of the entry to start reading after when the consumer group is created.
+
+					By default, the entries already in the stream are read. Use `$` to only read the entries added after the group
+					is created. This has no effect when the group already exists.
+					"""
+				required: false
+				type: string: {
+					default: "0"
+					examples: ["$"]
+				}
+			}
+		}
+	}
+	tls: {
+		description: """
+			TLS options for the Redis connection.
+
+			Certificates are verified against the system trust store, unless `ca_file` is set.
+			"""
+		required: false
+		type: object: options: {
+			ca_file: {
+				description: """
+					Absolute path to a CA certificate file, in PEM format.
+
+					When set, the certificate presented by the server is verified against this CA instead of the system trust
+					store.
+					"""
+				required: false
+				type: string: examples: ["/path/to/certificate_authority.crt"]
+			}
+			crt_file: {
+				description: """
+					Absolute path to a certificate file used to identify this client, in PEM format.
+
+					If this is set, `key_file` must also be set.
+					"""
+				required: false
+				type: string: examples: ["/path/to/client_certificate.crt"]
+			}
+			enabled: {
+				description: """
+					Whether or not to require TLS for the connection.
+
+					When enabled, a `redis://` URL is connected to as if it were `rediss://`.
+					"""
+				required: false
+				type: bool: default: false
+			}
+			key_file: {
+				description: """
+					Absolute path to a private key file used to identify this client, in PEM format.
+
+					If this is set, `crt_file` must also be set.
+					"""
+				required: false
+				type: string: examples: ["/path/to/client_certificate.key"]
+			}
+			verify_certificate: {
+				description: """
+					Enables certificate verification.
+
+					If enabled, the certificate presented by the server must be valid and must match its hostname.
+
+					By default, certificates are verified, unless `url` is a `rediss://` URL with the `#insecure` fragment.
+
+					Do NOT set this to `false` unless you understand the risks of not verifying the validity of certificates.
+					"""
+				required: false
+				type: bool: {}
+			}
+		}
+	}
+	truncate_payloads: {
+		description: """
+			Whether to truncate the message payloads larger than `max_payload_bytes` rather than dropping them.
+
+			The events of a truncated payload have the field set by `truncated_field` set to `true`. As a truncated
+			payload can rarely be decoded, this can only be used with the `bytes` codec.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	truncated_field: {
+		description: """
+			Overrides the name of the log field used to mark the events of a truncated payload.
+
+			This is only added when `truncate_payloads` is set.
+
+			By default, `"truncated"` is used.
+			"""
+		required: false
+		type: string: {
+			default: "truncated"
+			examples: ["truncated"]
+		}
+	}
 	url: {
 		description: """
 			The Redis URL to connect to.
 
 			The URL must take the form of `protocol://server:port/db` where the `protocol` can either be `redis` or `rediss` for connections secured using TLS.
+
+			When `sentinel` is set, the server is replaced by the master discovered through the sentinels, while the
+			database, credentials, and TLS settings of the URL still apply.
 			"""
 		required: true
 		type: string: examples: ["redis://127.0.0.1:6379/0"]
 	}
+	username: {
+		description: """
+			The username to authenticate with, overriding the username set in `url`.
+
+			This is used with Redis ACLs. Unlike in `url`, it does not need to be URL-encoded.
+			"""
+		required: false
+		type: string: examples: ["vector"]
+	}
 }
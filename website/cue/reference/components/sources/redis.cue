@@ -5,7 +5,7 @@ components: sources: redis: {
 
 	features: {
 		auto_generated:   true
-		acknowledgements: false
+		acknowledgements: true
 		collect: {
 			checkpoint: enabled: false
 			tls: enabled:        false
@@ -82,6 +82,40 @@ components: sources: redis: {
 					default: null
 				}
 			}
+			redis_event: {
+				description: "The keyspace notification event, such as `set`, `del` or `expired`, for the `keyspace` data type"
+				required:    false
+				common:      false
+				type: string: {
+					examples: ["set", "del", "expired"]
+					default: null
+				}
+			}
+			redis_db: {
+				description: "The database of the changed key, for the `keyspace` data type"
+				required:    false
+				common:      false
+				type: uint: {
+					examples: [0]
+					default: null
+					unit:    null
+				}
+			}
+			redis_channel: {
+				description: "The Redis channel the message was published to, for the `channel` data type"
+				required:    false
+				common:      false
+				type: string: {
+					examples: ["some_channel"]
+					default: null
+				}
+			}
+			truncated: {
+				description: "Set to `true` when the message payload was truncated to `max_payload_bytes`. The name of the field is set by `truncated_field`"
+				required:    false
+				common:      false
+				type: bool: default: null
+			}
 		}
 	}
 
@@ -94,5 +128,20 @@ components: sources: redis: {
 				API.
 				"""
 		}
+		acknowledgements: {
+			title: "Acknowledgements"
+			body:  """
+				When the `stream` data type is used with end-to-end acknowledgements enabled, an entry is only
+				acknowledged with `XACK` once its events have been delivered by the sinks. Entries whose events fail
+				to be delivered are read and sent again, and rejected entries are left in the consumer group's
+				pending entries list.
+
+				The `list` data type supports acknowledgements when `list.processing_list` is set. Each element is
+				then moved into the processing list as it is read, and only removed from it once its events have been
+				delivered. Elements whose events fail to be delivered are pushed back onto the list they were read
+				from. The `channel` data type does not support acknowledgements, since Redis does not keep published
+				messages.
+				"""
+		}
 	}
 }